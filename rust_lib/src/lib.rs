@@ -9,21 +9,127 @@
 //! - Pythonとの連携インターフェース
 //! - キャンセル機能
 //! - 進捗報告
+//! - 見かけ上/実ディスク使用量の切り替えとハードリンクの重複排除
+//! - ワークスティーリングによる並列走査
+//! - globパターンや`.gitignore`による除外フィルタ
 
 use pyo3::prelude::*;
 use pyo3::exceptions::PyIOError;
+use crossbeam_deque::{Injector, Steal, Stealer, Worker as DequeWorker};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::fmt;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// サイズの集計方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeMode {
+    /// `metadata.len()` による見かけ上のサイズ
+    #[default]
+    Apparent,
+    /// ディスク上の実際の割り当てサイズ（ブロック単位、`du` と同じ考え方）
+    OnDisk,
+}
+
+/// `mode` に応じてエントリのサイズを計算する
+fn entry_size(path: &Path, metadata: &fs::Metadata, mode: SizeMode) -> u64 {
+    match mode {
+        SizeMode::Apparent => metadata.len(),
+        SizeMode::OnDisk => on_disk_size(path, metadata),
+    }
+}
+
+#[cfg(unix)]
+fn on_disk_size(_path: &Path, metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(windows)]
+fn on_disk_size(path: &Path, metadata: &fs::Metadata) -> u64 {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetCompressedFileSizeW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut high: u32 = 0;
+    let low = unsafe { GetCompressedFileSizeW(wide.as_ptr(), &mut high) };
+    if low == u32::MAX {
+        // GetCompressedFileSizeW が失敗した場合は見かけ上のサイズにフォールバック
+        metadata.len()
+    } else {
+        ((high as u64) << 32) | (low as u64)
+    }
+}
+
+/// エントリが同一デバイス・同一inode（Windowsではファイルインデックス）を
+/// 複数の場所から参照されうるハードリンクかどうかを判定する
+fn has_multiple_links(metadata: &fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.nlink() > 1
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        metadata.number_of_links().unwrap_or(1) > 1
+    }
+}
+
+/// エントリを一意に識別するキー（Unix: (dev, ino)、Windows: (volume_serial, file_index)）
+fn file_identity(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        Some((metadata.volume_serial_number()? as u64, metadata.file_index()?))
+    }
+}
+
+/// 指定した識別子が未訪問なら `visited` に記録して `true` を返す
+///
+/// シンボリックリンク経由で辿り着いたディレクトリと、通常の再帰で直接辿り着いた
+/// ディレクトリの両方をこの関数経由で記録することで、同じディレクトリが別の経路
+/// (シンボリックリンク/直接参照のどちらでも)から複数回訪問されても二重に計上しない。
+/// 識別子が取得できない場合は判定できないため常に `true` を返す。
+fn mark_visited(identity: Option<(u64, u64)>, visited: &mut HashSet<(u64, u64)>) -> bool {
+    match identity {
+        Some(id) => visited.insert(id),
+        None => true,
+    }
+}
+
+/// 既に数えたハードリンク先であれば `false` を返し、サイズの二重計上を防ぐ
+///
+/// リンク数が1のエントリは複数箇所から参照され得ないため、セットを小さく保つために
+/// ハードリンクされたエントリ（リンク数 > 1）だけを `seen` に登録する。
+fn should_count(metadata: &fs::Metadata, seen: &mut HashSet<(u64, u64)>) -> bool {
+    if !has_multiple_links(metadata) {
+        return true;
+    }
+    match file_identity(metadata) {
+        Some(identity) => seen.insert(identity),
+        None => true,
+    }
+}
 
 /// ディレクトリサイズ計算時のエラー型
-#[derive(Debug)]
+///
+/// `cause` を `Arc<io::Error>` で包むことで、同じエラーを複数の観測者（進捗コールバックや
+/// 複数ワーカー）へ安価に共有・複製できるようにしている。
+#[derive(Debug, Clone)]
 pub enum DirSizeError {
     /// I/Oエラー（ファイルアクセスエラーなど）
-    IoError { path: String, cause: io::Error },
+    IoError { path: String, cause: Arc<io::Error> },
     /// 処理がキャンセルされた
     Cancelled,
 }
@@ -44,7 +150,7 @@ impl fmt::Display for DirSizeError {
 impl std::error::Error for DirSizeError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            DirSizeError::IoError { cause, .. } => Some(cause),
+            DirSizeError::IoError { cause, .. } => Some(cause.as_ref()),
             _ => None,
         }
     }
@@ -54,7 +160,22 @@ impl From<(io::Error, &Path)> for DirSizeError {
     fn from((err, path): (io::Error, &Path)) -> Self {
         DirSizeError::IoError {
             path: path.to_string_lossy().into_owned(),
-            cause: err,
+            cause: Arc::new(err),
+        }
+    }
+}
+
+impl DirSizeError {
+    /// 複数のワーカーが返したエラーを1つにまとめる。空なら `None`
+    ///
+    /// 並列走査をキャンセルすると複数のワーカーがそれぞれ `Cancelled` を返しうるが、
+    /// ワーカーが返しうるエラーは `Cancelled` だけ（I/Oエラーは `ScanReport.inaccessible`
+    /// に個別に積まれ、ここには流れてこない）なので、1件でもあれば `Cancelled` を返せば十分。
+    fn from_many(errors: Vec<DirSizeError>) -> Option<DirSizeError> {
+        if errors.is_empty() {
+            None
+        } else {
+            Some(DirSizeError::Cancelled)
         }
     }
 }
@@ -65,59 +186,415 @@ impl From<DirSizeError> for PyErr {
     }
 }
 
+/// ディレクトリスキャンの結果をまとめた構造体
+///
+/// アクセス拒否などで読み取れないサブツリーがあっても処理を中断せず、
+/// 読み取れた分の合計サイズと、読み取れなかったパスの一覧を両方保持する。
+#[pyclass]
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// 走査できたファイルの合計サイズ（バイト単位）
+    #[pyo3(get)]
+    pub total_size: u64,
+    /// 走査できたファイル数
+    #[pyo3(get)]
+    pub files_counted: u64,
+    /// アクセスできなかったパスとそのエラー種別
+    pub inaccessible: Vec<(PathBuf, io::ErrorKind)>,
+    /// `follow_symlinks = false` のために辿らなかったシンボリックリンクの数
+    #[pyo3(get)]
+    pub symlinks_skipped: u64,
+    /// `follow_symlinks = true` で辿った結果、循環が検出されたシンボリックリンクのパス
+    pub symlink_cycles: Vec<PathBuf>,
+    /// 除外パターンまたは`.gitignore`にマッチして除外された合計バイト数
+    #[pyo3(get)]
+    pub excluded_size: u64,
+    /// 除外パターンまたは`.gitignore`にマッチして除外されたファイル数
+    #[pyo3(get)]
+    pub excluded_files: u64,
+}
+
+#[pymethods]
+impl ScanReport {
+    /// アクセスできなかったパスを `(パス, エラー種別)` の文字列タプルの一覧として返す
+    #[getter]
+    fn inaccessible(&self) -> Vec<(String, String)> {
+        self.inaccessible
+            .iter()
+            .map(|(path, kind)| (path.to_string_lossy().into_owned(), kind.to_string()))
+            .collect()
+    }
+
+    /// 循環が検出されたシンボリックリンクのパスを文字列の一覧として返す
+    #[getter]
+    fn symlink_cycles(&self) -> Vec<String> {
+        self.symlink_cycles
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect()
+    }
+}
+
+impl ScanReport {
+    fn add_file(&mut self, size: u64) {
+        self.total_size += size;
+        self.files_counted += 1;
+    }
+
+    fn add_inaccessible(&mut self, path: PathBuf, kind: io::ErrorKind) {
+        self.inaccessible.push((path, kind));
+    }
+
+    fn add_symlink_skipped(&mut self) {
+        self.symlinks_skipped += 1;
+    }
+
+    fn add_symlink_cycle(&mut self, path: PathBuf) {
+        self.symlink_cycles.push(path);
+    }
+
+    fn add_excluded(&mut self, size: u64, count: u64) {
+        self.excluded_size += size;
+        self.excluded_files += count;
+    }
+
+    fn merge(&mut self, other: ScanReport) {
+        self.total_size += other.total_size;
+        self.files_counted += other.files_counted;
+        self.inaccessible.extend(other.inaccessible);
+        self.symlinks_skipped += other.symlinks_skipped;
+        self.symlink_cycles.extend(other.symlink_cycles);
+        self.excluded_size += other.excluded_size;
+        self.excluded_files += other.excluded_files;
+    }
+}
+
+/// シンボリックリンクの扱い方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// シンボリックリンクは辿らず、`ScanReport::symlinks_skipped` に計上するだけにする
+    #[default]
+    Skip,
+    /// シンボリックリンクの指す先まで辿る（循環は検出して1度しか計上しない）
+    Follow,
+}
+
+/// パス区切り `/` を含まないパターンを、どの階層のパスでもマッチするように `**/` で始まる
+/// 形に補う（`/` を含むパターンはユーザーが意図的に階層を指定したものとみなし、そのまま使う）
+///
+/// `.gitignore` の `node_modules` のようなベア名は深さに関係なくマッチするのが自然な挙動
+/// であり、globパターンもそれに合わせることで、同じ除外設定を書いたときの挙動が
+/// `.gitignore`方式とglob方式とで食い違わないようにする。
+fn anchor_pattern(pattern: &str) -> String {
+    if pattern.contains('/') {
+        pattern.to_string()
+    } else {
+        format!("**/{}", pattern)
+    }
+}
+
+/// globパターンと（任意で）`.gitignore` のルールをまとめてコンパイルしたマッチャー
+///
+/// スキャン開始前に一度だけ構築し、以降は再帰呼び出しやワーカースレッド間で
+/// `Arc` 越しに共有する。ディレクトリを降りるたびに再パースすることはしない。
+/// [`ScanOptions`] 経由で公開関数のシグネチャに現れるため `pub` にしているが、
+/// フィールドや構築・判定ロジックはこのクレート内だけで使う実装詳細のままにしておく。
+pub struct ExclusionMatcher {
+    root: PathBuf,
+    globs: GlobSet,
+    gitignore: Option<Gitignore>,
+}
+
+impl ExclusionMatcher {
+    /// `patterns` のglobと、`respect_gitignore` が真なら `root` 配下に見つかった
+    /// すべての `.gitignore` ファイルを取り込んでマッチャーを構築する
+    ///
+    /// `cancelled` が立っている間は `.gitignore` の事前走査を打ち切る（巨大なツリーでも
+    /// キャンセル要求に応答できるようにするため）。
+    fn build(
+        root: &Path,
+        patterns: &[String],
+        respect_gitignore: bool,
+        cancelled: &AtomicBool,
+    ) -> Result<ExclusionMatcher, globset::Error> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(&anchor_pattern(pattern))?);
+        }
+        let globs = builder.build()?;
+
+        let gitignore = if respect_gitignore {
+            let mut gi_builder = GitignoreBuilder::new(root);
+            for gitignore_path in find_gitignore_files(root, &globs, cancelled) {
+                gi_builder.add(gitignore_path);
+            }
+            gi_builder.build().ok()
+        } else {
+            None
+        };
+
+        Ok(ExclusionMatcher { root: root.to_path_buf(), globs, gitignore })
+    }
+
+    /// `path` が除外対象かどうかを判定する（`is_dir` はgitignoreのディレクトリ専用パターン判定に使う）
+    ///
+    /// globパターンはスキャンルートからの相対パスに対して判定する。
+    fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.globs.is_match(path.strip_prefix(&self.root).unwrap_or(path)) {
+            return true;
+        }
+        match &self.gitignore {
+            Some(gitignore) => gitignore.matched(path, is_dir).is_ignore(),
+            None => false,
+        }
+    }
+}
+
+/// スキャン全体を通して変化しない設定をまとめた構造体
+///
+/// `mode`・`symlink_policy`・`exclusion` は再帰呼び出しのたびに同じ値を渡すことになるため、
+/// 個別の引数として増やし続けるのではなくまとめて引き回す。
+#[derive(Clone, Default)]
+pub struct ScanOptions {
+    /// 見かけ上のサイズかディスク上の割り当てサイズか
+    pub mode: SizeMode,
+    /// シンボリックリンクを辿るかどうか
+    pub symlink_policy: SymlinkPolicy,
+    /// globパターン/`.gitignore`の除外マッチャー（`None`なら除外しない）
+    pub exclusion: Option<Arc<ExclusionMatcher>>,
+}
+
+impl ScanOptions {
+    /// 除外フィルタなしで `mode`/`symlink_policy` だけを指定する
+    pub fn new(mode: SizeMode, symlink_policy: SymlinkPolicy) -> Self {
+        ScanOptions { mode, symlink_policy, exclusion: None }
+    }
+}
+
+/// 進捗報告用コールバックの型（複数スレッドから呼ばれるため `Send + Sync`）
+type ProgressCallback = Arc<dyn Fn(&str, u64) + Send + Sync>;
+
+/// `root` 配下に存在する `.gitignore` ファイルをすべて探す
+///
+/// `.gitignore` 自体はまだ構築できていない段階での事前走査だが、globパターンに
+/// マッチしたディレクトリは本スキャンでもどのみち丸ごと除外されるため、その配下までは
+/// 降りない。`cancelled` が立っていれば即座に走査を打ち切り、それまでに見つかった分を返す。
+fn find_gitignore_files(root: &Path, glob_excludes: &GlobSet, cancelled: &AtomicBool) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_dir() {
+                        if glob_excludes.is_match(path.strip_prefix(root).unwrap_or(&path)) {
+                            continue;
+                        }
+                        stack.push(path);
+                    } else if metadata.is_file() && path.file_name().is_some_and(|n| n == ".gitignore") {
+                        found.push(path);
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+/// 除外にマッチしたサブツリーの合計バイト数とファイル数を数え上げる
+///
+/// 除外されたディレクトリの中までパターン判定を続ける必要はないため、
+/// ここでは純粋にサイズを合計するだけで個々のエントリを除外判定しない。
+fn tally_excluded_subtree(path: &Path, mode: SizeMode) -> (u64, u64) {
+    let mut size = 0u64;
+    let mut count = 0u64;
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_symlink() {
+                    continue;
+                } else if metadata.is_file() {
+                    size += entry_size(&entry_path, &metadata, mode);
+                    count += 1;
+                } else if metadata.is_dir() {
+                    let (sub_size, sub_count) = tally_excluded_subtree(&entry_path, mode);
+                    size += sub_size;
+                    count += sub_count;
+                }
+            }
+        }
+    }
+    (size, count)
+}
+
 /// Pythonから呼び出し可能なディレクトリサイズ計算関数
 ///
 /// # 引数
 /// * `path` - サイズを計算するディレクトリのパス
+/// * `on_disk` - `true` の場合、見かけ上のサイズではなくディスク上の割り当てサイズを使う
+/// * `follow_symlinks` - `true` の場合、シンボリックリンクの指す先まで辿る（循環は検出する）
 ///
 /// # 戻り値
-/// * `PyResult<u64>` - 計算されたサイズ（バイト単位）またはエラー
+/// * `PyResult<ScanReport>` - 合計サイズとアクセス不能パスの一覧を含むスキャン結果
 #[pyfunction]
-fn get_dir_size_py(path: String) -> PyResult<u64> {
+#[pyo3(signature = (path, on_disk=false, follow_symlinks=false))]
+fn get_dir_size_py(path: String, on_disk: bool, follow_symlinks: bool) -> PyResult<ScanReport> {
     let path_buf = PathBuf::from(path);
-    get_dir_size(&path_buf).map_err(|e| e.into())
+    let mode = if on_disk { SizeMode::OnDisk } else { SizeMode::Apparent };
+    let symlink_policy = if follow_symlinks { SymlinkPolicy::Follow } else { SymlinkPolicy::Skip };
+    get_dir_size(&path_buf, ScanOptions::new(mode, symlink_policy)).map_err(|e| e.into())
 }
 
-/// アクセス拒否を示す特別な値を返す関数
+/// Pythonから呼び出し可能な、glob/`.gitignore`による除外フィルタ付きディレクトリサイズ計算関数
+///
+/// # 引数
+/// * `path` - サイズを計算するディレクトリのパス
+/// * `patterns` - 除外するglobパターンの一覧。`/` を含まないパターン（例: `"node_modules"`）は
+///   `.gitignore` と同様にどの深さのパスでもマッチする。特定の階層だけに限定したい場合は
+///   `"src/node_modules"` のように `/` を含む形で書く
+/// * `respect_gitignore` - `true` の場合、`path` 配下で見つかった `.gitignore` も除外ルールとして使う
 ///
 /// # 戻り値
-/// * `u64` - アクセス拒否を示す特別な値（u64::MAX）
+/// * `PyResult<ScanReport>` - 合計サイズと、除外されたバイト数・ファイル数を含むスキャン結果
 #[pyfunction]
-fn get_access_denied_value() -> PyResult<u64> {
-    Ok(u64::MAX)
+#[pyo3(signature = (path, patterns, respect_gitignore=false))]
+fn get_dir_size_filtered_py(path: String, patterns: Vec<String>, respect_gitignore: bool) -> PyResult<ScanReport> {
+    let path_buf = PathBuf::from(path);
+    // このPython向けエントリポイントはキャンセル機能を持たないため、常に立たない旗を渡す
+    let cancelled = AtomicBool::new(false);
+    let matcher = ExclusionMatcher::build(&path_buf, &patterns, respect_gitignore, &cancelled)
+        .map_err(|e| PyIOError::new_err(format!("除外パターンのコンパイルに失敗しました: {}", e)))?;
+    let options = ScanOptions {
+        mode: SizeMode::Apparent,
+        symlink_policy: SymlinkPolicy::Skip,
+        exclusion: Some(Arc::new(matcher)),
+    };
+    get_dir_size(&path_buf, options).map_err(|e| e.into())
+}
+
+/// シンボリックリンクの指す先を解決する
+///
+/// `policy` が `Skip` の場合は辿らずに `report.symlinks_skipped` を増やすだけにする。
+/// `Follow` の場合はリンク先のメタデータを取得し、それがディレクトリであれば
+/// `visited` に識別子（dev, ino）を記録して循環を検出する。既に記録済みであれば
+/// 循環とみなし `report.symlink_cycles` に記録して `None` を返す。
+///
+/// 戻り値が `Some` のときだけ、呼び出し元はリンク先を通常のファイル/ディレクトリとして
+/// 処理を続ける。
+fn resolve_symlink(
+    entry_path: &Path,
+    policy: SymlinkPolicy,
+    visited: &mut HashSet<(u64, u64)>,
+    report: &mut ScanReport,
+) -> Option<fs::Metadata> {
+    if policy == SymlinkPolicy::Skip {
+        report.add_symlink_skipped();
+        return None;
+    }
+
+    let target_metadata = match fs::metadata(entry_path) {
+        Ok(m) => m,
+        Err(e) => {
+            report.add_inaccessible(entry_path.to_path_buf(), e.kind());
+            return None;
+        }
+    };
+
+    if target_metadata.is_dir() && !mark_visited(file_identity(&target_metadata), visited) {
+        report.add_symlink_cycle(entry_path.to_path_buf());
+        return None;
+    }
+
+    Some(target_metadata)
 }
 
 /// ディレクトリサイズを再帰的に計算する関数
 ///
+/// アクセス拒否などで読み取れないサブディレクトリがあっても処理を継続し、
+/// 読み取れた分のサイズとアクセス不能パスの一覧を `ScanReport` として返す。
+/// ルートディレクトリ自体が読み取れない場合のみエラーを返す。
+/// 同一のハードリンク先（デバイス・inode、またはWindowsのファイルインデックス）を
+/// 指す複数のエントリはスキャン全体で一度しか計上しない。
+///
 /// # 引数
 /// * `path` - サイズを計算するディレクトリのパス
+/// * `options` - サイズの数え方・シンボリックリンクの扱い・除外マッチャーをまとめた設定
 ///
 /// # 戻り値
-/// * `Result<u64, DirSizeError>` - 計算されたサイズ（バイト単位）またはエラー
+/// * `Result<ScanReport, DirSizeError>` - スキャン結果またはエラー
 ///
 /// # エラー
-/// * `DirSizeError::IoError` - ファイルシステム操作中のI/Oエラー
-pub fn get_dir_size(path: &Path) -> Result<u64, DirSizeError> {
-    let mut total_size = 0;
-    let mut access_denied = false;
+/// * `DirSizeError::IoError` - `path` 自体の読み取りに失敗した場合
+pub fn get_dir_size(path: &Path, options: ScanOptions) -> Result<ScanReport, DirSizeError> {
+    let mut seen = HashSet::new();
+    let mut visited = HashSet::new();
+    get_dir_size_internal(path, &options, &mut seen, &mut visited)
+}
+
+// 内部実装用の関数（ハードリンク・シンボリックリンクの重複排除セットをスキャン全体で共有する）
+fn get_dir_size_internal(
+    path: &Path,
+    options: &ScanOptions,
+    seen: &mut HashSet<(u64, u64)>,
+    visited: &mut HashSet<(u64, u64)>,
+) -> Result<ScanReport, DirSizeError> {
+    let mode = options.mode;
+    let symlink_policy = options.symlink_policy;
+    let mut report = ScanReport::default();
 
     if let Ok(entries) = fs::read_dir(path) {
         for entry in entries.flatten() {  // エラーのあるエントリはスキップ
-            let path = entry.path();
+            let entry_path = entry.path();
             if let Ok(metadata) = entry.metadata() {
-                if metadata.is_file() {
-                    total_size += metadata.len();
-                } else if metadata.is_dir() {
-                    match get_dir_size(&path) {
-                        Ok(size) => {
-                            if size == u64::MAX {
-                                access_denied = true;
-                            } else {
-                                total_size += size;
+                if let Some(matcher) = &options.exclusion {
+                    if matcher.is_excluded(&entry_path, metadata.is_dir()) {
+                        if metadata.is_dir() {
+                            let (size, count) = tally_excluded_subtree(&entry_path, mode);
+                            report.add_excluded(size, count);
+                        } else if metadata.is_file() {
+                            report.add_excluded(entry_size(&entry_path, &metadata, mode), 1);
+                        }
+                        continue;
+                    }
+                }
+                if metadata.is_symlink() {
+                    if let Some(target_metadata) = resolve_symlink(&entry_path, symlink_policy, visited, &mut report) {
+                        if target_metadata.is_file() {
+                            if should_count(&target_metadata, seen) {
+                                report.add_file(entry_size(&entry_path, &target_metadata, mode));
+                            }
+                        } else if target_metadata.is_dir() {
+                            match get_dir_size_internal(&entry_path, options, seen, visited) {
+                                Ok(sub_report) => report.merge(sub_report),
+                                Err(DirSizeError::IoError { cause, .. }) => {
+                                    report.add_inaccessible(entry_path, cause.kind());
+                                },
+                                Err(DirSizeError::Cancelled) => {
+                                    // get_dir_size はキャンセル機能を持たないため発生しない
+                                }
                             }
+                        }
+                    }
+                } else if metadata.is_file() {
+                    if should_count(&metadata, seen) {
+                        report.add_file(entry_size(&entry_path, &metadata, mode));
+                    }
+                } else if metadata.is_dir() {
+                    if !mark_visited(file_identity(&metadata), visited) {
+                        continue;
+                    }
+                    match get_dir_size_internal(&entry_path, options, seen, visited) {
+                        Ok(sub_report) => report.merge(sub_report),
+                        Err(DirSizeError::IoError { cause, .. }) => {
+                            report.add_inaccessible(entry_path, cause.kind());
                         },
-                        Err(_) => {
-                            access_denied = true;
+                        Err(DirSizeError::Cancelled) => {
+                            // get_dir_size はキャンセル機能を持たないため発生しない
                         }
                     }
                 }
@@ -127,27 +604,45 @@ pub fn get_dir_size(path: &Path) -> Result<u64, DirSizeError> {
         return Err((io::Error::new(io::ErrorKind::PermissionDenied, "アクセスが拒否されました"), path).into());
     }
 
-    if access_denied {
-        Ok(u64::MAX)  // 特別な値でアクセス拒否を示す
-    } else {
-        Ok(total_size)
-    }
+    Ok(report)
 }
 
 /// ディレクトリサイズを再帰的に計算する関数（進捗報告とキャンセル機能付き）
 ///
+/// アクセス拒否などで読み取れないサブディレクトリがあっても処理を継続し、
+/// 読み取れた分のサイズとアクセス不能パスの一覧を `ScanReport` として返す。
+///
 /// # 引数
 /// * `path` - サイズを計算するディレクトリのパス
+/// * `options` - サイズの数え方・シンボリックリンクの扱い・除外マッチャーをまとめた設定
 /// * `cancelled` - キャンセルフラグ
 /// * `progress_callback` - 進捗報告用コールバック関数
 ///
 /// # 戻り値
-/// * `Result<u64, DirSizeError>` - 計算されたサイズ（バイト単位）またはエラー
+/// * `Result<ScanReport, DirSizeError>` - スキャン結果またはエラー
 pub fn get_dir_size_with_progress<F>(
     path: &Path,
+    options: ScanOptions,
     cancelled: Arc<AtomicBool>,
     mut progress_callback: F
-) -> Result<u64, DirSizeError>
+) -> Result<ScanReport, DirSizeError>
+where
+    F: FnMut(&str, u64)
+{
+    let mut seen = HashSet::new();
+    let mut visited = HashSet::new();
+    get_dir_size_with_progress_internal(path, &options, cancelled, &mut progress_callback, &mut seen, &mut visited)
+}
+
+// 内部実装用の関数（再帰呼び出し用）
+fn get_dir_size_with_progress_internal<F>(
+    path: &Path,
+    options: &ScanOptions,
+    cancelled: Arc<AtomicBool>,
+    progress_callback: &mut F,
+    seen: &mut HashSet<(u64, u64)>,
+    visited: &mut HashSet<(u64, u64)>,
+) -> Result<ScanReport, DirSizeError>
 where
     F: FnMut(&str, u64)
 {
@@ -156,8 +651,9 @@ where
         return Err(DirSizeError::Cancelled);
     }
 
-    let mut total_size = 0;
-    let mut access_denied = false;
+    let mode = options.mode;
+    let symlink_policy = options.symlink_policy;
+    let mut report = ScanReport::default();
 
     if let Ok(entries) = fs::read_dir(path) {
         for entry in entries.flatten() {  // エラーのあるエントリはスキップ
@@ -166,36 +662,59 @@ where
                 return Err(DirSizeError::Cancelled);
             }
 
-            let path = entry.path();
+            let entry_path = entry.path();
             if let Ok(metadata) = entry.metadata() {
-                if metadata.is_file() {
-                    let file_size = metadata.len();
-                    total_size += file_size;
-                    
-                    // 進捗報告
-                    progress_callback(path.to_string_lossy().as_ref(), file_size);
-                } else if metadata.is_dir() {
-                    // サブディレクトリの処理は再帰ではなく、自前で実装
-                    let subdir_result = get_dir_size_with_progress_internal(
-                        &path, 
-                        cancelled.clone(),
-                        &mut |subpath, size| progress_callback(subpath, size)
-                    );
-                    
-                    match subdir_result {
-                        Ok(size) => {
-                            if size == u64::MAX {
-                                access_denied = true;
-                            } else {
-                                total_size += size;
+                if let Some(matcher) = &options.exclusion {
+                    if matcher.is_excluded(&entry_path, metadata.is_dir()) {
+                        if metadata.is_dir() {
+                            let (size, count) = tally_excluded_subtree(&entry_path, mode);
+                            report.add_excluded(size, count);
+                        } else if metadata.is_file() {
+                            report.add_excluded(entry_size(&entry_path, &metadata, mode), 1);
+                        }
+                        continue;
+                    }
+                }
+                if metadata.is_symlink() {
+                    if let Some(target_metadata) = resolve_symlink(&entry_path, symlink_policy, visited, &mut report) {
+                        if target_metadata.is_file() {
+                            if should_count(&target_metadata, seen) {
+                                let file_size = entry_size(&entry_path, &target_metadata, mode);
+                                report.add_file(file_size);
+                                progress_callback(entry_path.to_string_lossy().as_ref(), file_size);
                             }
-                        },
+                        } else if target_metadata.is_dir() {
+                            match get_dir_size_with_progress_internal(&entry_path, options, cancelled.clone(), progress_callback, seen, visited) {
+                                Ok(sub_report) => report.merge(sub_report),
+                                Err(DirSizeError::Cancelled) => {
+                                    return Err(DirSizeError::Cancelled);
+                                },
+                                Err(DirSizeError::IoError { cause, .. }) => {
+                                    report.add_inaccessible(entry_path, cause.kind());
+                                },
+                            }
+                        }
+                    }
+                } else if metadata.is_file() {
+                    if should_count(&metadata, seen) {
+                        let file_size = entry_size(&entry_path, &metadata, mode);
+                        report.add_file(file_size);
+
+                        // 進捗報告
+                        progress_callback(entry_path.to_string_lossy().as_ref(), file_size);
+                    }
+                } else if metadata.is_dir() {
+                    if !mark_visited(file_identity(&metadata), visited) {
+                        continue;
+                    }
+                    match get_dir_size_with_progress_internal(&entry_path, options, cancelled.clone(), progress_callback, seen, visited) {
+                        Ok(sub_report) => report.merge(sub_report),
                         Err(DirSizeError::Cancelled) => {
                             return Err(DirSizeError::Cancelled);
                         },
-                        Err(_) => {
-                            access_denied = true;
-                        }
+                        Err(DirSizeError::IoError { cause, .. }) => {
+                            report.add_inaccessible(entry_path, cause.kind());
+                        },
                     }
                 }
             }
@@ -204,75 +723,271 @@ where
         return Err((io::Error::new(io::ErrorKind::PermissionDenied, "アクセスが拒否されました"), path).into());
     }
 
-    if access_denied {
-        println!("  Some subdirectories were inaccessible");
-        Ok(u64::MAX)  // 特別な値でアクセス拒否を示す
-    } else {
-        Ok(total_size)
-    }
+    Ok(report)
 }
 
-// 内部実装用の関数（再帰呼び出し用）
-fn get_dir_size_with_progress_internal<F>(
-    path: &Path,
+/// 並列走査のワーカー間で共有する状態
+struct ParallelScanState {
+    mode: SizeMode,
+    symlink_policy: SymlinkPolicy,
+    /// globパターン/`.gitignore`の除外マッチャー（スキャン開始前に一度だけ構築し、ワーカー間で共有する）
+    exclusion: Option<Arc<ExclusionMatcher>>,
     cancelled: Arc<AtomicBool>,
-    progress_callback: &mut F
-) -> Result<u64, DirSizeError>
-where
-    F: FnMut(&str, u64)
-{
-    // キャンセルされていないか確認
-    if cancelled.load(Ordering::Relaxed) {
-        return Err(DirSizeError::Cancelled);
+    progress_callback: ProgressCallback,
+    injector: Injector<PathBuf>,
+    /// まだ処理が完了していないディレクトリの数（終了判定に使う）
+    pending: AtomicUsize,
+    total_size: AtomicU64,
+    files_counted: AtomicU64,
+    inaccessible: Mutex<Vec<(PathBuf, io::ErrorKind)>>,
+    seen: Mutex<HashSet<(u64, u64)>>,
+    /// 訪問済みディレクトリの識別子（直接辿ったものとシンボリックリンク経由のものの両方。循環・二重計上の検出用。ワーカー間で共有する）
+    visited: Mutex<HashSet<(u64, u64)>>,
+    symlinks_skipped: AtomicU64,
+    symlink_cycles: Mutex<Vec<PathBuf>>,
+    excluded_size: AtomicU64,
+    excluded_files: AtomicU64,
+}
+
+impl ParallelScanState {
+    /// 指定した識別子が未訪問なら `visited` に記録して `true` を返す（[`mark_visited`] の並列版）
+    fn mark_visited(&self, identity: Option<(u64, u64)>) -> bool {
+        match identity {
+            Some(id) => self.visited.lock().unwrap().insert(id),
+            None => true,
+        }
     }
+}
 
-    let mut total_size = 0;
-    let mut access_denied = false;
+/// ローカルキューとインジェクタ、他ワーカーのスティーラーから次のディレクトリを探す
+fn find_task(
+    local: &DequeWorker<PathBuf>,
+    injector: &Injector<PathBuf>,
+    stealers: &[Stealer<PathBuf>],
+) -> Option<PathBuf> {
+    if let Some(task) = local.pop() {
+        return Some(task);
+    }
+    loop {
+        match injector.steal_batch_and_pop(local) {
+            Steal::Success(task) => return Some(task),
+            Steal::Retry => continue,
+            Steal::Empty => break,
+        }
+    }
+    for stealer in stealers {
+        loop {
+            match stealer.steal() {
+                Steal::Success(task) => return Some(task),
+                Steal::Retry => continue,
+                Steal::Empty => break,
+            }
+        }
+    }
+    None
+}
 
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries.flatten() {  // エラーのあるエントリはスキップ
-            // 定期的にキャンセルフラグをチェック
-            if cancelled.load(Ordering::Relaxed) {
-                return Err(DirSizeError::Cancelled);
+/// 1つのワーカースレッドのメインループ
+fn parallel_scan_worker(
+    state: &ParallelScanState,
+    local: DequeWorker<PathBuf>,
+    stealers: &[Stealer<PathBuf>],
+) -> Result<(), DirSizeError> {
+    loop {
+        if state.cancelled.load(Ordering::Relaxed) {
+            return Err(DirSizeError::Cancelled);
+        }
+
+        match find_task(&local, &state.injector, stealers) {
+            Some(dir) => {
+                scan_one_dir(state, &dir, &local);
+                // このディレクトリの処理が完了した（サブディレクトリは新しいタスクとして計上済み）
+                state.pending.fetch_sub(1, Ordering::SeqCst);
+            },
+            None => {
+                if state.pending.load(Ordering::SeqCst) == 0 {
+                    return Ok(());
+                }
+                // 他のワーカーがタスクを投入中の可能性があるので少し待って再試行する
+                std::thread::yield_now();
             }
+        }
+    }
+}
 
-            let path = entry.path();
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_file() {
-                    let file_size = metadata.len();
-                    total_size += file_size;
-                    
-                    // 進捗報告
-                    progress_callback(path.to_string_lossy().as_ref(), file_size);
-                } else if metadata.is_dir() {
-                    match get_dir_size_with_progress_internal(&path, cancelled.clone(), progress_callback) {
-                        Ok(size) => {
-                            if size == u64::MAX {
-                                access_denied = true;
-                            } else {
-                                total_size += size;
-                            }
-                        },
-                        Err(DirSizeError::Cancelled) => {
-                            return Err(DirSizeError::Cancelled);
-                        },
-                        Err(_) => {
-                            access_denied = true;
-                        }
+/// ハードリンクの重複を排除しつつファイルのサイズを計上し、進捗を報告する
+fn count_file(state: &ParallelScanState, entry_path: &Path, metadata: &fs::Metadata) {
+    let counted = {
+        let mut seen = state.seen.lock().unwrap();
+        should_count(metadata, &mut seen)
+    };
+    if counted {
+        let file_size = entry_size(entry_path, metadata, state.mode);
+        state.total_size.fetch_add(file_size, Ordering::Relaxed);
+        state.files_counted.fetch_add(1, Ordering::Relaxed);
+        (state.progress_callback)(entry_path.to_string_lossy().as_ref(), file_size);
+    }
+}
+
+/// [`resolve_symlink`] の並列版。循環検出用の `visited` セットをワーカー間で共有する
+fn resolve_symlink_parallel(state: &ParallelScanState, entry_path: &Path) -> Option<fs::Metadata> {
+    if state.symlink_policy == SymlinkPolicy::Skip {
+        state.symlinks_skipped.fetch_add(1, Ordering::Relaxed);
+        return None;
+    }
+
+    let target_metadata = match fs::metadata(entry_path) {
+        Ok(m) => m,
+        Err(e) => {
+            state.inaccessible.lock().unwrap().push((entry_path.to_path_buf(), e.kind()));
+            return None;
+        }
+    };
+
+    if target_metadata.is_dir() && !state.mark_visited(file_identity(&target_metadata)) {
+        state.symlink_cycles.lock().unwrap().push(entry_path.to_path_buf());
+        return None;
+    }
+
+    Some(target_metadata)
+}
+
+/// 1つのディレクトリ直下のエントリを処理し、サブディレクトリを作業キューに積む
+fn scan_one_dir(state: &ParallelScanState, path: &Path, local: &DequeWorker<PathBuf>) {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            state.inaccessible.lock().unwrap().push((path.to_path_buf(), e.kind()));
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {  // エラーのあるエントリはスキップ
+        let entry_path = entry.path();
+        if let Ok(metadata) = entry.metadata() {
+            if let Some(matcher) = &state.exclusion {
+                if matcher.is_excluded(&entry_path, metadata.is_dir()) {
+                    if metadata.is_dir() {
+                        let (size, count) = tally_excluded_subtree(&entry_path, state.mode);
+                        state.excluded_size.fetch_add(size, Ordering::Relaxed);
+                        state.excluded_files.fetch_add(count, Ordering::Relaxed);
+                    } else if metadata.is_file() {
+                        state.excluded_size.fetch_add(entry_size(&entry_path, &metadata, state.mode), Ordering::Relaxed);
+                        state.excluded_files.fetch_add(1, Ordering::Relaxed);
+                    }
+                    continue;
+                }
+            }
+            if metadata.is_symlink() {
+                if let Some(target_metadata) = resolve_symlink_parallel(state, &entry_path) {
+                    if target_metadata.is_file() {
+                        count_file(state, &entry_path, &target_metadata);
+                    } else if target_metadata.is_dir() {
+                        state.pending.fetch_add(1, Ordering::SeqCst);
+                        local.push(entry_path);
                     }
                 }
+            } else if metadata.is_file() {
+                count_file(state, &entry_path, &metadata);
+            } else if metadata.is_dir() {
+                if !state.mark_visited(file_identity(&metadata)) {
+                    continue;
+                }
+                state.pending.fetch_add(1, Ordering::SeqCst);
+                local.push(entry_path);
             }
         }
+    }
+}
+
+/// ディレクトリサイズをワークスティーリングで並列に計算する関数
+///
+/// ディレクトリ1つを1タスクとして共有デック（インジェクタ）に積み、各ワーカーが
+/// 自分のローカルキューを空にしたら他ワーカーから盗んで処理を続ける。
+/// `threads == 1` のときは単一スレッド版 [`get_dir_size_with_progress`] にフォールバックする。
+///
+/// # 引数
+/// * `path` - サイズを計算するディレクトリのパス
+/// * `options` - サイズの数え方・シンボリックリンクの扱い・除外マッチャーをまとめた設定
+/// * `threads` - 使用するワーカースレッド数（0の場合は `std::thread::available_parallelism()` を使う）
+/// * `cancelled` - キャンセルフラグ（各ワーカーのループ内で定期的にチェックされる）
+/// * `progress_callback` - 進捗報告用コールバック（複数スレッドから呼ばれるため `Send + Sync`）
+///
+/// # 戻り値
+/// * `Result<ScanReport, DirSizeError>` - スキャン結果またはエラー
+pub fn get_dir_size_parallel(
+    path: &Path,
+    options: ScanOptions,
+    threads: usize,
+    cancelled: Arc<AtomicBool>,
+    progress_callback: ProgressCallback,
+) -> Result<ScanReport, DirSizeError> {
+    let thread_count = if threads == 0 {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
     } else {
+        threads
+    };
+
+    if thread_count <= 1 {
+        let callback = progress_callback.clone();
+        return get_dir_size_with_progress(path, options, cancelled, move |p, s| callback(p, s));
+    }
+
+    // ルート自体が読み取れない場合は即座にエラーを返す（単一スレッド版と同じ契約）
+    if fs::read_dir(path).is_err() {
         return Err((io::Error::new(io::ErrorKind::PermissionDenied, "アクセスが拒否されました"), path).into());
     }
 
-    if access_denied {
-        println!("  Some subdirectories were inaccessible");
-        Ok(u64::MAX)  // 特別な値でアクセス拒否を示す
-    } else {
-        Ok(total_size)
+    let state = ParallelScanState {
+        mode: options.mode,
+        symlink_policy: options.symlink_policy,
+        exclusion: options.exclusion,
+        cancelled,
+        progress_callback,
+        injector: Injector::new(),
+        pending: AtomicUsize::new(1),
+        total_size: AtomicU64::new(0),
+        files_counted: AtomicU64::new(0),
+        inaccessible: Mutex::new(Vec::new()),
+        seen: Mutex::new(HashSet::new()),
+        visited: Mutex::new(HashSet::new()),
+        symlinks_skipped: AtomicU64::new(0),
+        symlink_cycles: Mutex::new(Vec::new()),
+        excluded_size: AtomicU64::new(0),
+        excluded_files: AtomicU64::new(0),
+    };
+    state.injector.push(path.to_path_buf());
+
+    let workers: Vec<DequeWorker<PathBuf>> = (0..thread_count).map(|_| DequeWorker::new_fifo()).collect();
+    let stealers: Vec<Stealer<PathBuf>> = workers.iter().map(|w| w.stealer()).collect();
+
+    let worker_errors: Mutex<Vec<DirSizeError>> = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for worker in workers {
+            let state = &state;
+            let stealers = &stealers;
+            let worker_errors = &worker_errors;
+            scope.spawn(move || {
+                if let Err(e) = parallel_scan_worker(state, worker, stealers) {
+                    worker_errors.lock().unwrap().push(e);
+                }
+            });
+        }
+    });
+
+    if let Some(e) = DirSizeError::from_many(worker_errors.into_inner().unwrap()) {
+        return Err(e);
     }
+
+    Ok(ScanReport {
+        total_size: state.total_size.load(Ordering::Relaxed),
+        files_counted: state.files_counted.load(Ordering::Relaxed),
+        inaccessible: state.inaccessible.into_inner().unwrap(),
+        symlinks_skipped: state.symlinks_skipped.load(Ordering::Relaxed),
+        symlink_cycles: state.symlink_cycles.into_inner().unwrap(),
+        excluded_size: state.excluded_size.load(Ordering::Relaxed),
+        excluded_files: state.excluded_files.load(Ordering::Relaxed),
+    })
 }
 
 /// Pythonから呼び出し可能なキャンセル機能付きディレクトリサイズ計算関数
@@ -281,31 +996,40 @@ where
 /// * `path` - サイズを計算するディレクトリのパス
 /// * `cancel_ptr` - キャンセルフラグへのポインタ
 /// * `callback` - 進捗報告用コールバック関数
+/// * `on_disk` - `true` の場合、見かけ上のサイズではなくディスク上の割り当てサイズを使う
+/// * `threads` - 使用するワーカースレッド数（0の場合は自動検出、1の場合は単一スレッド版を使用）。デフォルトは自動検出
+/// * `follow_symlinks` - `true` の場合、シンボリックリンクの指す先まで辿る（循環は検出する）
 ///
 /// # 戻り値
-/// * `PyResult<u64>` - 計算されたサイズ（バイト単位）またはエラー
+/// * `PyResult<ScanReport>` - 合計サイズとアクセス不能パスの一覧を含むスキャン結果
 #[pyfunction]
-fn get_dir_size_with_cancel_py(_py: Python, path: String, cancel_ptr: usize, callback: PyObject) -> PyResult<u64> {
+#[pyo3(signature = (path, cancel_ptr, callback, on_disk=false, threads=0, follow_symlinks=false))]
+fn get_dir_size_with_cancel_py(_py: Python, path: String, cancel_ptr: usize, callback: PyObject, on_disk: bool, threads: usize, follow_symlinks: bool) -> PyResult<ScanReport> {
     let path_buf = PathBuf::from(path);
     let cancelled = unsafe { Arc::from_raw(cancel_ptr as *const AtomicBool) };
-    
+
     // Arcのクローンを作成して元のArcを忘れない（メモリリーク防止）
     let cancelled_clone = cancelled.clone();
     std::mem::forget(cancelled);
-    
-    // Pythonコールバックをラップする関数
-    let progress_wrapper = move |path: &str, size: u64| {
+
+    // Pythonコールバックをラップする関数（複数スレッドから呼ばれうるのでMutexで直列化する）
+    let callback = Arc::new(Mutex::new(callback));
+    let progress_wrapper: ProgressCallback = Arc::new(move |path: &str, size: u64| {
         Python::with_gil(|py| {
-            let _ = callback.call1(py, (path, size));
+            let _ = callback.lock().unwrap().call1(py, (path, size));
         });
-    };
-    
+    });
+
+    let mode = if on_disk { SizeMode::OnDisk } else { SizeMode::Apparent };
+    let symlink_policy = if follow_symlinks { SymlinkPolicy::Follow } else { SymlinkPolicy::Skip };
+    let options = ScanOptions::new(mode, symlink_policy);
+
     // 処理実行
-    let result = get_dir_size_with_progress(&path_buf, cancelled_clone, progress_wrapper);
-    
+    let result = get_dir_size_parallel(&path_buf, options, threads, cancelled_clone, progress_wrapper);
+
     // 結果を返す
     match result {
-        Ok(size) => Ok(size),
+        Ok(report) => Ok(report),
         Err(e) => Err(e.into()),
     }
 }
@@ -356,8 +1080,9 @@ fn release_cancel_flag(ptr: usize) -> PyResult<()> {
 /// Python モジュールの初期化関数
 #[pymodule]
 fn rust_lib(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<ScanReport>()?;
     m.add_function(wrap_pyfunction!(get_dir_size_py, m)?)?;
-    m.add_function(wrap_pyfunction!(get_access_denied_value, m)?)?;
+    m.add_function(wrap_pyfunction!(get_dir_size_filtered_py, m)?)?;
     m.add_function(wrap_pyfunction!(get_dir_size_with_cancel_py, m)?)?;
     m.add_function(wrap_pyfunction!(create_cancel_flag, m)?)?;
     m.add_function(wrap_pyfunction!(set_cancel_flag, m)?)?;
@@ -384,14 +1109,16 @@ mod tests {
 
         drop(file);
 
-        let size = get_dir_size(dir_path).unwrap();
-        assert!(size > 0);
+        let report = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip)).unwrap();
+        assert!(report.total_size > 0);
+        assert_eq!(report.files_counted, 1);
+        assert!(report.inaccessible.is_empty());
     }
 
     /// 存在しないディレクトリに対するテスト
     #[test]
     fn test_get_dir_size_not_found() {
-        let result = get_dir_size(Path::new("nonexistent_directory"));
+        let result = get_dir_size(Path::new("nonexistent_directory"), ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip));
         assert!(result.is_err());
 
         match result.unwrap_err() {
@@ -399,29 +1126,57 @@ mod tests {
                 assert_eq!(path, "nonexistent_directory");
                 assert_eq!(cause.kind(), io::ErrorKind::PermissionDenied);
             },
+            other => panic!("unexpected error variant: {:?}", other),
         }
     }
 
     /// アクセス権限がないディレクトリに対するテスト
+    ///
+    /// `/root`のような固定パスはCIやコンテナではrootユーザーで実行されることが多く、
+    /// その場合はパーミッションビットに関係なく読めてしまい不安定になる。そのため
+    /// テスト自身が所有する一時ディレクトリ配下にパーミッション000のサブディレクトリを
+    /// 作って使い、それでもなお読めてしまう（root権限で実行されている等）場合は
+    /// パーミッションを検証しようがないのでテストをスキップする。
+    #[cfg(unix)]
     #[test]
     fn test_get_dir_size_permission_denied() {
-        // 管理者権限が必要なディレクトリ (通常はアクセスできない)
-        #[cfg(windows)]
-        let dir_path = Path::new("C:\\Windows\\System32\\config"); // 例
-        #[cfg(not(windows))]
-        let dir_path = Path::new("/root"); // 例
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        let restricted_dir = dir_path.join("restricted");
+        fs::create_dir(&restricted_dir).unwrap();
+        fs::set_permissions(&restricted_dir, fs::Permissions::from_mode(0o000)).unwrap();
+
+        if fs::read_dir(&restricted_dir).is_ok() {
+            eprintln!("skipping test_get_dir_size_permission_denied: running with privileges that ignore permission bits");
+            return;
+        }
+
+        let report = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip))
+            .expect("the root directory itself should still be readable");
+        assert!(!report.inaccessible.is_empty(), "Expected at least one inaccessible path");
+    }
+
+    /// アクセス権限がないディレクトリに対するテスト（Windows版）
+    ///
+    /// 管理者権限が必要なディレクトリは環境によって結果が異なる可能性があるため、
+    /// 読み取り不能パスが記録されたレポートか、エラーのどちらかを許容する。
+    #[cfg(windows)]
+    #[test]
+    fn test_get_dir_size_permission_denied() {
+        let dir_path = Path::new("C:\\Windows\\System32\\config");
+
+        let result = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip));
 
-        let result = get_dir_size(dir_path);
-        
-        // Windows環境では権限によって結果が異なる可能性があるため、
-        // エラーまたはu64::MAXのどちらかを許容
         match result {
-            Ok(size) => {
-                assert_eq!(size, u64::MAX, "Expected access denied value");
+            Ok(report) => {
+                assert!(!report.inaccessible.is_empty(), "Expected at least one inaccessible path");
             },
             Err(DirSizeError::IoError { path: _, cause }) => {
                 assert_eq!(cause.kind(), io::ErrorKind::PermissionDenied);
             },
+            Err(other) => panic!("unexpected error variant: {:?}", other),
         }
     }
 
@@ -452,9 +1207,289 @@ mod tests {
         };
 
         // 実行
-        let result = get_dir_size_with_progress(dir_path, cancelled, progress_callback);
+        let result = get_dir_size_with_progress(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip), cancelled, progress_callback);
         
         // キャンセルエラーを期待
         assert!(matches!(result, Err(DirSizeError::Cancelled)));
     }
+
+    /// 並列走査が単一スレッド版と同じ合計サイズを計算することのテスト
+    #[test]
+    fn test_get_dir_size_parallel_matches_single_threaded() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        for i in 0..4 {
+            let subdir = dir_path.join(format!("subdir_{}", i));
+            fs::create_dir(&subdir).unwrap();
+
+            for j in 0..8 {
+                let file_path = subdir.join(format!("file_{}.txt", j));
+                let mut file = File::create(&file_path).unwrap();
+                writeln!(file, "Test content").unwrap();
+            }
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let sequential = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip)).unwrap();
+        let parallel = get_dir_size_parallel(
+            dir_path,
+            ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip),
+            4,
+            cancelled,
+            Arc::new(|_path: &str, _size: u64| {}),
+        ).unwrap();
+
+        assert_eq!(sequential.total_size, parallel.total_size);
+        assert_eq!(sequential.files_counted, parallel.files_counted);
+    }
+
+    /// 並列走査をキャンセルした場合、複数ワーカーがそれぞれ `Cancelled` を返しても
+    /// 単一の `DirSizeError::Cancelled` にまとまることのテスト
+    #[test]
+    fn test_get_dir_size_parallel_cancellation_collapses_to_single_cancelled() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        for i in 0..4 {
+            let subdir = dir_path.join(format!("subdir_{}", i));
+            fs::create_dir(&subdir).unwrap();
+
+            for j in 0..8 {
+                let file_path = subdir.join(format!("file_{}.txt", j));
+                let mut file = File::create(&file_path).unwrap();
+                writeln!(file, "Test content").unwrap();
+            }
+        }
+
+        // キャンセルフラグを設定して即時キャンセル（全ワーカーが Cancelled を返す想定）
+        let cancelled = Arc::new(AtomicBool::new(true));
+        let result = get_dir_size_parallel(
+            dir_path,
+            ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip),
+            4,
+            cancelled,
+            Arc::new(|_path: &str, _size: u64| {}),
+        );
+
+        assert!(matches!(result, Err(DirSizeError::Cancelled)), "expected a single Cancelled, got {:?}", result);
+    }
+
+    /// ハードリンクされたファイルは同じ(デバイス, inode)を指すため、一度しか計上されないことのテスト
+    #[cfg(unix)]
+    #[test]
+    fn test_hard_link_counted_once() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        let original = dir_path.join("original.txt");
+
+        let mut file = File::create(&original).unwrap();
+        writeln!(file, "Hello, world!").unwrap();
+        drop(file);
+
+        fs::hard_link(&original, dir_path.join("hard_link.txt")).unwrap();
+
+        let report = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip)).unwrap();
+        assert_eq!(report.files_counted, 1, "the hard-linked copy should not be counted again");
+    }
+
+    /// `SizeMode::OnDisk` では見かけ上のサイズではなく、ディスク上の割り当てサイズが使われることのテスト
+    #[test]
+    fn test_on_disk_size_mode() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        let file_path = dir_path.join("test_file.txt");
+
+        let mut file = File::create(&file_path).unwrap();
+        writeln!(file, "Hello, world!").unwrap();
+        drop(file);
+
+        let apparent = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip)).unwrap();
+        let on_disk = get_dir_size(dir_path, ScanOptions::new(SizeMode::OnDisk, SymlinkPolicy::Skip)).unwrap();
+
+        assert_eq!(apparent.files_counted, 1);
+        assert_eq!(on_disk.files_counted, 1);
+        assert!(on_disk.total_size > 0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(on_disk.total_size, fs::metadata(&file_path).unwrap().blocks() * 512);
+        }
+    }
+
+    /// シンボリックリンクを辿らない設定では、リンク自体が計上されずスキップ数として記録されることのテスト
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_skip_by_default() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        let target_dir = dir_path.join("target");
+        fs::create_dir(&target_dir).unwrap();
+        let mut file = File::create(target_dir.join("file.txt")).unwrap();
+        writeln!(file, "Hello").unwrap();
+        drop(file);
+
+        std::os::unix::fs::symlink(&target_dir, dir_path.join("link")).unwrap();
+
+        let report = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Skip)).unwrap();
+        assert_eq!(report.symlinks_skipped, 1);
+        assert_eq!(report.files_counted, 1, "only the real file should be counted, not through the link");
+    }
+
+    /// シンボリックリンクが自己参照する循環を検出できることのテスト
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cycle_detection() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        let looping_link = dir_path.join("loop");
+
+        std::os::unix::fs::symlink(dir_path, &looping_link).unwrap();
+
+        let report = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Follow)).unwrap();
+        assert_eq!(report.symlink_cycles.len(), 1);
+    }
+
+    /// 同じディレクトリが直接の再帰とシンボリックリンク経由の両方から辿り着ける場合に、
+    /// 中身のファイルが二重に計上されないことのテスト
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_to_directly_reachable_dir_not_double_counted() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        let data_dir = dir_path.join("data");
+        fs::create_dir(&data_dir).unwrap();
+        let mut file = File::create(data_dir.join("file.txt")).unwrap();
+        writeln!(file, "Hello").unwrap();
+        drop(file);
+
+        // data_dir はスキャンルートの直下から直接辿り着けるが、link からも辿り着ける
+        std::os::unix::fs::symlink(&data_dir, dir_path.join("link")).unwrap();
+
+        let report = get_dir_size(dir_path, ScanOptions::new(SizeMode::Apparent, SymlinkPolicy::Follow)).unwrap();
+        assert_eq!(report.files_counted, 1, "file reachable both directly and via a symlink must be counted once");
+    }
+
+    /// globパターンにマッチしたディレクトリが除外され、バイト数・ファイル数が別集計されることのテスト
+    #[test]
+    fn test_exclusion_by_glob_pattern() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let kept_file = dir_path.join("keep.txt");
+        let mut file = File::create(&kept_file).unwrap();
+        writeln!(file, "kept").unwrap();
+        drop(file);
+
+        let excluded_dir = dir_path.join("node_modules");
+        fs::create_dir(&excluded_dir).unwrap();
+        let mut file = File::create(excluded_dir.join("dep.js")).unwrap();
+        writeln!(file, "excluded content").unwrap();
+        drop(file);
+
+        let patterns = vec!["**/node_modules".to_string()];
+        let matcher = ExclusionMatcher::build(dir_path, &patterns, false, &AtomicBool::new(false)).unwrap();
+        let options = ScanOptions {
+            mode: SizeMode::Apparent,
+            symlink_policy: SymlinkPolicy::Skip,
+            exclusion: Some(Arc::new(matcher)),
+        };
+        let report = get_dir_size(dir_path, options).unwrap();
+
+        assert_eq!(report.files_counted, 1, "only keep.txt should be counted");
+        assert_eq!(report.excluded_files, 1);
+        assert!(report.excluded_size > 0);
+    }
+
+    /// `/` を含まないベアなglobパターンが、`.gitignore`と同じく深さに関係なくマッチすることのテスト
+    #[test]
+    fn test_exclusion_by_bare_glob_pattern_matches_any_depth() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let excluded_dir = dir_path.join("node_modules");
+        fs::create_dir(&excluded_dir).unwrap();
+        let mut file = File::create(excluded_dir.join("dep.js")).unwrap();
+        writeln!(file, "excluded content").unwrap();
+        drop(file);
+
+        let patterns = vec!["node_modules".to_string()];
+        let matcher = ExclusionMatcher::build(dir_path, &patterns, false, &AtomicBool::new(false)).unwrap();
+        let options = ScanOptions {
+            mode: SizeMode::Apparent,
+            symlink_policy: SymlinkPolicy::Skip,
+            exclusion: Some(Arc::new(matcher)),
+        };
+        let report = get_dir_size(dir_path, options).unwrap();
+
+        assert_eq!(report.excluded_files, 1, "bare pattern \"node_modules\" should match like .gitignore does");
+    }
+
+    /// `.gitignore` に書かれたパターンが除外ルールとして使われることのテスト
+    #[test]
+    fn test_exclusion_respects_gitignore() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut gitignore = File::create(dir_path.join(".gitignore")).unwrap();
+        writeln!(gitignore, "*.log").unwrap();
+        drop(gitignore);
+
+        let mut file = File::create(dir_path.join("app.log")).unwrap();
+        writeln!(file, "log content").unwrap();
+        drop(file);
+
+        let mut file = File::create(dir_path.join("keep.txt")).unwrap();
+        writeln!(file, "kept").unwrap();
+        drop(file);
+
+        let matcher = ExclusionMatcher::build(dir_path, &[], true, &AtomicBool::new(false)).unwrap();
+        let options = ScanOptions {
+            mode: SizeMode::Apparent,
+            symlink_policy: SymlinkPolicy::Skip,
+            exclusion: Some(Arc::new(matcher)),
+        };
+        let report = get_dir_size(dir_path, options).unwrap();
+
+        // keep.txt と .gitignore 自身は数えられ、app.log だけが除外される
+        assert_eq!(report.files_counted, 2, "only app.log should be excluded");
+        assert_eq!(report.excluded_files, 1);
+    }
+
+    /// `.gitignore` の事前走査がglobパターンで除外済みのディレクトリの中までは降りないことのテスト
+    #[test]
+    fn test_find_gitignore_files_skips_excluded_subtree() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let excluded_dir = dir_path.join("node_modules");
+        fs::create_dir(&excluded_dir).unwrap();
+        let mut gitignore = File::create(excluded_dir.join(".gitignore")).unwrap();
+        writeln!(gitignore, "*.log").unwrap();
+        drop(gitignore);
+
+        let mut builder = GlobSetBuilder::new();
+        builder.add(Glob::new("**/node_modules").unwrap());
+        let glob_excludes = builder.build().unwrap();
+
+        let found = find_gitignore_files(dir_path, &glob_excludes, &AtomicBool::new(false));
+        assert!(found.is_empty(), "gitignore inside an excluded directory should not be found");
+    }
+
+    /// `.gitignore` の事前走査がキャンセルフラグを見て即座に打ち切られることのテスト
+    #[test]
+    fn test_find_gitignore_files_respects_cancellation() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut gitignore = File::create(dir_path.join(".gitignore")).unwrap();
+        writeln!(gitignore, "*.log").unwrap();
+        drop(gitignore);
+
+        let glob_excludes = GlobSetBuilder::new().build().unwrap();
+        let cancelled = AtomicBool::new(true);
+
+        let found = find_gitignore_files(dir_path, &glob_excludes, &cancelled);
+        assert!(found.is_empty(), "scan should stop immediately when already cancelled");
+    }
 }
\ No newline at end of file